@@ -21,26 +21,53 @@ unsafe impl Send for Library {}
 unsafe impl Sync for Library {}
 
 impl Library {
-    /// Load a library, forever.
+    /// Load a library, forever, using [`LoadFlags::SEARCH_DEFAULT_DIRS`] on Windows.
+    ///
+    /// This avoids the classic DLL preloading/planting attack, where a hijacked copy of a
+    /// library sitting in the current working directory gets loaded instead of the real one.
+    /// The application directory is still searched.  If you need different search behavior,
+    /// use [`Library::load_with`] directly.
     ///
     /// | OS        | Behavior |
     /// | --------- | -------- |
-    /// | Windows   | `LoadLibraryW(path)`
+    /// | Windows   | `LoadLibraryExW(path, null, LOAD_LIBRARY_SEARCH_DEFAULT_DIRS)`
     /// | Unix      | `dlopen(path, ...)`
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        #[cfg(windows)] let flags = LoadFlags::SEARCH_DEFAULT_DIRS;
+        #[cfg(unix)] let flags = LoadFlags::NONE;
+        Self::load_with(path, flags, UnixLoadFlags::NONE)
+    }
+
+    /// Load a library, forever, with explicit control over platform-specific loading behavior.
+    ///
+    /// `flags` map directly to `LoadLibraryExW`'s `dwFlags` parameter on Windows; see
+    /// [`LoadFlags`] for the available bits.  `unix_flags` map to `dlopen`'s `mode` parameter
+    /// on Unix; see [`UnixLoadFlags`] for the available bits.  Each parameter is ignored on
+    /// the other platform.
+    ///
+    /// | OS        | Behavior |
+    /// | --------- | -------- |
+    /// | Windows   | `LoadLibraryExW(path, null, flags)`
+    /// | Unix      | `dlopen(path, unix_flags | (RTLD_NOW or RTLD_LAZY))`
+    pub fn load_with(path: impl AsRef<Path>, flags: LoadFlags, unix_flags: UnixLoadFlags) -> Result<Self> {
         let path = path.as_ref();
 
         #[cfg(windows)] let handle = {
             use std::os::windows::ffi::OsStrExt;
+            let _ = unix_flags; // no Windows analogue to dlopen's mode flags
             let filename = path.as_os_str().encode_wide().chain([0].iter().copied()).collect::<Vec<u16>>();
-            unsafe { LoadLibraryW(filename.as_ptr()) }
+            unsafe { LoadLibraryExW(filename.as_ptr(), null_mut(), flags.bits()) }
         };
 
         #[cfg(unix)] let handle = {
             use std::os::unix::ffi::OsStrExt;
+            let _ = flags; // no Unix analogue to Win32's DLL search path flags
             let filename = path.as_os_str().as_bytes().iter().copied().chain([0].iter().copied()).collect::<Vec<u8>>();
+            // dlopen requires exactly one of RTLD_LAZY/RTLD_NOW; default to RTLD_LAZY unless
+            // the caller explicitly asked for RTLD_NOW.
+            let binding = if unix_flags.bits() & RTLD_NOW != 0 { 0 } else { RTLD_LAZY };
             let _ = unsafe { dlerror() }; // clear error code
-            unsafe { dlopen(filename.as_ptr() as _, RTLD_LAZY) }
+            unsafe { dlopen(filename.as_ptr() as _, binding | unix_flags.bits()) }
         };
 
         if handle != null_mut() {
@@ -77,6 +104,40 @@ impl Library {
         Ok(Self(handle))
     }
 
+    /// The global/default symbol scope, mirroring glibc's `RTLD_DEFAULT` pseudo-handle.
+    ///
+    /// Symbol lookups via [`sym`](Self::sym)/[`sym_opt`](Self::sym_opt) on the returned
+    /// [`Library`] search the normal global scope (the executable itself, plus every library
+    /// loaded with `RTLD_GLOBAL`) instead of any one specific library.
+    ///
+    /// # Platform
+    ///
+    /// | OS        | Behavior |
+    /// | --------- | -------- |
+    /// | Windows   | `Err(...)`, unsupported
+    /// | Unix      | wraps `RTLD_DEFAULT`
+    pub fn default_scope() -> Result<Self> {
+        #[cfg(unix)] { Ok(Self(RTLD_DEFAULT)) }
+        #[cfg(windows)] { Err(io::Error::new(io::ErrorKind::Unsupported, "RTLD_DEFAULT has no equivalent on Windows")) }
+    }
+
+    /// The "next" library in the search order after the calling module, mirroring glibc's
+    /// `RTLD_NEXT` pseudo-handle.
+    ///
+    /// This exists for symbol interposition: a shim that overrides e.g. `malloc` can resolve
+    /// the *real* `malloc` via `Library::next()?.sym(...)` and call through to it.
+    ///
+    /// # Platform
+    ///
+    /// | OS        | Behavior |
+    /// | --------- | -------- |
+    /// | Windows   | `Err(...)`, unsupported
+    /// | Unix      | wraps `RTLD_NEXT`
+    pub fn next() -> Result<Self> {
+        #[cfg(unix)] { Ok(Self(RTLD_NEXT)) }
+        #[cfg(windows)] { Err(io::Error::new(io::ErrorKind::Unsupported, "RTLD_NEXT has no equivalent on Windows")) }
+    }
+
     /// Load a symbol from the library.
     /// Note that the symbol name must end with '\0'.
     /// Limiting yourself to basic ASCII is also likely wise.
@@ -131,6 +192,31 @@ impl Library {
         }
     }
 
+    /// Load a symbol from the library, returning a [`Symbol`] that borrows this [`Library`]
+    /// instead of a bare transmuted pointer with no tie to it.
+    /// Note that the symbol name must end with '\0'.
+    /// Limiting yourself to basic ASCII is also likely wise.
+    ///
+    /// This is the safer counterpart to [`sym`](Self::sym)/[`sym_opt`](Self::sym_opt) for
+    /// callers who don't want to manage a raw transmuted pointer themselves: since `Symbol`
+    /// borrows `self`, the borrow checker prevents using it after an [`OwnedLibrary`] it came
+    /// from has been closed.
+    ///
+    /// # Safety
+    ///
+    /// This function implicitly transmutes!  Use extreme caution.
+    ///
+    /// # Platform
+    ///
+    /// | OS        | Behavior |
+    /// | --------- | -------- |
+    /// | Windows   | `GetProcAddress(..., name)`
+    /// | Unix      | `dlsym(..., name)`
+    pub unsafe fn get<'a, T>(&'a self, name: impl AsRef<str>) -> io::Result<Symbol<'a, T>> {
+        let address = self.sym::<T>(name)?;
+        Ok(Symbol { address, _lib: std::marker::PhantomData })
+    }
+
     /// Load a symbol from the library by ordinal.
     ///
     /// # Safety
@@ -203,16 +289,264 @@ impl Library {
     }
 }
 
+/// An owned library handle that unloads the library on drop, unlike [`Library`] which
+/// deliberately leaks forever.
+///
+/// This exists for plugin hosts that reload modules at runtime, where leaking one handle
+/// per load is unacceptable.  [`Deref`](std::ops::Deref)s to [`Library`], so `sym`/`sym_opt`/
+/// etc. work unchanged.
+///
+/// # Safety
+///
+/// Symbols from [`sym`](Library::sym)/[`sym_opt`](Library::sym_opt) have no lifetime
+/// tracking: dropping (or [`close`](Self::close)ing) an `OwnedLibrary` invalidates any
+/// symbols previously transmuted out of it.  Calling through a symbol after that is undefined
+/// behavior — it's on you to ensure nothing still does.  A [`Symbol`] from
+/// [`get`](Library::get) borrows the `OwnedLibrary`, so it can't outlive it.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct OwnedLibrary(Library);
+
+impl OwnedLibrary {
+    /// Load a library, returning a handle that unloads it on drop.
+    ///
+    /// See [`Library::load`] for the underlying loading behavior.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Library::load(path).map(Self)
+    }
+
+    /// Load a library with explicit control over platform-specific loading behavior, returning
+    /// a handle that unloads it on drop.
+    ///
+    /// See [`Library::load_with`] for the underlying loading behavior.
+    pub fn load_with(path: impl AsRef<Path>, flags: LoadFlags, unix_flags: UnixLoadFlags) -> Result<Self> {
+        Library::load_with(path, flags, unix_flags).map(Self)
+    }
+
+    /// Unload the library now, returning any error instead of silently ignoring it as
+    /// [`Drop`] does.
+    ///
+    /// # Platform
+    ///
+    /// | OS        | Behavior |
+    /// | --------- | -------- |
+    /// | Windows   | `FreeLibrary`
+    /// | Unix      | `dlclose`
+    pub fn close(self) -> Result<()> {
+        let handle = self.0;
+        std::mem::forget(self); // Drop would otherwise try to close it again
+        Self::free(handle)
+    }
+
+    /// Stop tracking this handle, converting it into the plain, leak-forever [`Library`] type.
+    pub fn leak(self) -> Library {
+        let handle = self.0;
+        std::mem::forget(self); // Drop must not close a handle we just promised to leak
+        handle
+    }
+
+    fn free(handle: Library) -> Result<()> {
+        #[cfg(windows)] {
+            if unsafe { FreeLibrary(handle.0) } != 0 {
+                Ok(())
+            } else {
+                Err(Error::last_os_error())
+            }
+        }
+        #[cfg(unix)] {
+            let _ = unsafe { dlerror() }; // clear error code
+            if unsafe { dlclose(handle.0) } == 0 {
+                Ok(())
+            } else {
+                Err(io::Error::new(io::ErrorKind::Other, unsafe { std::ffi::CStr::from_ptr(dlerror()) }.to_string_lossy()))
+            }
+        }
+    }
+}
+
+impl Drop for OwnedLibrary {
+    fn drop(&mut self) {
+        let _ = Self::free(self.0);
+    }
+}
+
+impl std::ops::Deref for OwnedLibrary {
+    type Target = Library;
+    fn deref(&self) -> &Library {
+        &self.0
+    }
+}
+
+/// A symbol resolved from a [`Library`], borrowing it so the borrow checker can catch
+/// use-after-close instead of leaving a dangling transmuted pointer.
+///
+/// Returned by [`Library::get`].  [`Deref`](std::ops::Deref)s to `T`, so function pointers
+/// get ordinary call syntax: `(*symbol)(args...)`.
+pub struct Symbol<'lib, T> {
+    address: T,
+    _lib: std::marker::PhantomData<&'lib Library>,
+}
+
+impl<'lib, T> std::ops::Deref for Symbol<'lib, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        &self.address
+    }
+}
+
+/// Flags controlling how [`Library::load_with`] searches for a library's dependencies.
+///
+/// These map directly onto the bits accepted by `LoadLibraryExW`'s `dwFlags` parameter.
+/// On Unix platforms there's no equivalent mechanism, so these are ignored there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoadFlags(u32);
+
+impl LoadFlags {
+    /// No flags: behaves like a bare `LoadLibraryW`, including the classic (insecure) search
+    /// order that consults the application directory and current working directory.
+    pub const NONE: Self = Self(0);
+
+    /// `LOAD_LIBRARY_SEARCH_APPLICATION_DIR` — search the application's install directory.
+    pub const SEARCH_APPLICATION_DIR: Self = Self(0x200);
+
+    /// `LOAD_LIBRARY_SEARCH_DEFAULT_DIRS` — search the application directory, `System32`, and
+    /// any directories added via `AddDllDirectory`, but *not* the current working directory.
+    pub const SEARCH_DEFAULT_DIRS: Self = Self(0x1000);
+
+    /// `LOAD_LIBRARY_SEARCH_DLL_LOAD_DIR` — search the directory the library itself is in.
+    /// Only meaningful when `path` is absolute.
+    pub const SEARCH_DLL_LOAD_DIR: Self = Self(0x100);
+
+    /// `LOAD_LIBRARY_SEARCH_SYSTEM32` — search `System32` only.
+    pub const SEARCH_SYSTEM32: Self = Self(0x800);
+
+    /// The raw `dwFlags` bits, as passed to `LoadLibraryExW`.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::ops::BitOr for LoadFlags {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for LoadFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Flags controlling how [`Library::load_with`] opens a library via `dlopen` on Unix.
+///
+/// These map directly onto the bits accepted by `dlopen`'s `mode` parameter.  On Windows
+/// there's no equivalent mechanism, so these are ignored there.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct UnixLoadFlags(c_int);
+
+impl UnixLoadFlags {
+    /// No flags: resolves symbols lazily (`RTLD_LAZY`) with the platform's default visibility,
+    /// identical to the crate's previous hardcoded behavior.
+    pub const NONE: Self = Self(0);
+
+    /// `RTLD_NOW` — resolve all undefined symbols in the library before `dlopen` returns,
+    /// instead of lazily on first use.  Useful for plugins that should fail to load rather
+    /// than crash later on a missing symbol.
+    pub const NOW: Self = Self(RTLD_NOW);
+
+    /// `RTLD_GLOBAL` — make the library's symbols available for relocation processing of
+    /// later-loaded libraries, as if it had been loaded with `RTLD_GLOBAL` from the start.
+    pub const GLOBAL: Self = Self(RTLD_GLOBAL);
+
+    /// `RTLD_LOCAL` — the opposite of `GLOBAL`; this is the default on most platforms, but
+    /// some (e.g. macOS) default to `GLOBAL`, so set this explicitly to be sure.
+    pub const LOCAL: Self = Self(RTLD_LOCAL);
+
+    /// `RTLD_NODELETE` — don't unload the library's symbols on close, even if the reference
+    /// count drops to zero; a later `dlopen` of the same path reuses the existing mapping.
+    pub const NODELETE: Self = Self(RTLD_NODELETE);
+
+    /// The raw `mode` bits, as passed to `dlopen`.
+    pub const fn bits(self) -> c_int {
+        self.0
+    }
+}
+
+impl std::ops::BitOr for UnixLoadFlags {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for UnixLoadFlags {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Build the platform-appropriate filename for a dynamically loaded library from a bare
+/// module name, so callers don't have to `#[cfg]` on the extension themselves.
+///
+/// | OS        | `library_filename("foo")` |
+/// | --------- | -------------------------- |
+/// | Windows   | `foo.dll`
+/// | macOS     | `libfoo.dylib`
+/// | other Unix| `libfoo.so`
+///
+/// Usable directly with [`Library::load`]: `Library::load(library_filename("ssl"))`.
+pub fn library_filename(name: impl AsRef<std::ffi::OsStr>) -> std::ffi::OsString {
+    let name = name.as_ref();
+    let mut filename = std::ffi::OsString::new();
+
+    #[cfg(windows)] {
+        filename.push(name);
+        filename.push(".dll");
+    }
+    #[cfg(target_os = "macos")] {
+        filename.push("lib");
+        filename.push(name);
+        filename.push(".dylib");
+    }
+    #[cfg(all(unix, not(target_os = "macos")))] {
+        filename.push("lib");
+        filename.push(name);
+        filename.push(".so");
+    }
+
+    filename
+}
+
 #[cfg(windows)] const ERROR_BAD_EXE_FORMAT : i32 = 0x00C1;
 #[cfg(windows)] const ERROR_MOD_NOT_FOUND  : i32 = 0x007E;
 #[cfg(windows)] extern "system" {
     fn GetProcAddress(hModule: *mut c_void, lpProcName: *const c_char) -> *mut c_void;
-    fn LoadLibraryW(lpFileName: *const u16) -> *mut c_void;
+    fn LoadLibraryExW(lpFileName: *const u16, hFile: *mut c_void, dwFlags: u32) -> *mut c_void;
+    fn FreeLibrary(hLibModule: *mut c_void) -> c_int;
 }
 
 #[cfg(unix)] const RTLD_LAZY : c_int = 1;
+// These are part of `UnixLoadFlags`'s public API surface, so (like `LoadFlags`'s Windows
+// constants) they're defined for every target, not just `cfg(unix)`.
+const RTLD_NOW : c_int = 2;
+
+#[cfg(target_os = "macos")]     const RTLD_GLOBAL : c_int = 0x8;
+#[cfg(not(target_os = "macos"))] const RTLD_GLOBAL : c_int = 0x100;
+
+#[cfg(target_os = "macos")]     const RTLD_LOCAL : c_int = 0x4;
+#[cfg(not(target_os = "macos"))] const RTLD_LOCAL : c_int = 0;
+
+#[cfg(target_os = "macos")]     const RTLD_NODELETE : c_int = 0x80;
+#[cfg(not(target_os = "macos"))] const RTLD_NODELETE : c_int = 0x1000;
+
+#[cfg(unix)] const RTLD_NEXT: *mut c_void = -1isize as *mut c_void;
+#[cfg(all(unix, target_os = "macos"))]     const RTLD_DEFAULT: *mut c_void = -2isize as *mut c_void;
+#[cfg(all(unix, not(target_os = "macos")))] const RTLD_DEFAULT: *mut c_void = null_mut();
+
 #[cfg(unix)] extern "C" {
     fn dlopen(filename: *const c_char, flags: c_int) -> *mut c_void;
     fn dlsym(handle: *mut c_void, symbol: *const c_char) -> *mut c_void;
+    fn dlclose(handle: *mut c_void) -> c_int;
     fn dlerror() -> *const c_char;
 }